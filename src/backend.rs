@@ -0,0 +1,227 @@
+//! Pluggable storage backends for the insert/read workload, so the same
+//! generated records can be benchmarked against more than just rusqlite.
+
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::PragmaConfig;
+
+/// A storage engine the generic insert/read harness can drive. Each handle
+/// is opened by, and used exclusively from, a single thread - callers open
+/// one handle per worker rather than sharing one across threads.
+pub trait Backend: Send + 'static {
+    /// Open (creating if necessary) the backend at `path`. `batch_size`
+    /// inserts are buffered before being made durable, mirroring the
+    /// BEGIN/COMMIT batching of the sqlite insert path; `0` means "buffer
+    /// everything until `flush` is called explicitly".
+    fn open(path: &str, batch_size: u64) -> anyhow::Result<Self>
+    where
+        Self: Sized;
+
+    /// Insert one record keyed by `key`.
+    fn insert(&mut self, key: &uuid::Uuid, value: &[u8]) -> anyhow::Result<()>;
+
+    /// Point lookup by key, for mixed read/write workloads.
+    fn get(&self, key: &uuid::Uuid) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Total number of records currently stored.
+    fn read_count(&self) -> anyhow::Result<u64>;
+
+    /// Make any buffered inserts durable.
+    fn flush(&mut self) -> anyhow::Result<()>;
+}
+
+/// Which `Backend` implementation to run the benchmark against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackendKind {
+    Sqlite,
+    Kv,
+}
+
+/// The SQLite-backed `Backend`. Holds a single connection for the lifetime
+/// of the handle (built through a one-connection r2d2 pool purely so it
+/// shares plumbing with the multi-threaded path) and batches inserts into
+/// explicit transactions exactly like `run_workload`.
+pub struct SqliteBackend {
+    pool: Pool<SqliteConnectionManager>,
+    batch_size: u64,
+    pending: u64,
+    in_txn: bool,
+}
+
+impl Backend for SqliteBackend {
+    fn open(path: &str, batch_size: u64) -> anyhow::Result<Self> {
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(SqliteConnectionManager::file(path))?;
+        {
+            let conn = pool.get()?;
+            PragmaConfig::default().apply(&conn)?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS kv(
+                    key BLOB PRIMARY KEY NOT NULL,
+                    value BLOB NOT NULL
+                )",
+                (),
+            )?;
+        }
+        Ok(Self {
+            pool,
+            batch_size,
+            pending: 0,
+            in_txn: false,
+        })
+    }
+
+    fn insert(&mut self, key: &uuid::Uuid, value: &[u8]) -> anyhow::Result<()> {
+        let conn = self.pool.get()?;
+        if !self.in_txn {
+            conn.execute_batch("BEGIN")?;
+            self.in_txn = true;
+        }
+        let mut stmt = conn.prepare_cached("INSERT INTO kv(key, value) VALUES (?, ?)")?;
+        stmt.execute((key.as_bytes().as_slice(), value))?;
+        self.pending += 1;
+        if self.batch_size != 0 && self.pending >= self.batch_size {
+            conn.execute_batch("COMMIT")?;
+            self.in_txn = false;
+            self.pending = 0;
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: &uuid::Uuid) -> anyhow::Result<Option<Vec<u8>>> {
+        let conn = self.pool.get()?;
+        let value = conn
+            .query_row(
+                "SELECT value FROM kv WHERE key = ?",
+                (key.as_bytes().as_slice(),),
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    fn read_count(&self) -> anyhow::Result<u64> {
+        let conn = self.pool.get()?;
+        let count: u64 = conn.query_row("SELECT count(*) FROM kv", [], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        if self.in_txn {
+            let conn = self.pool.get()?;
+            conn.execute_batch("COMMIT")?;
+            self.in_txn = false;
+            self.pending = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Process-wide registry of open KV log files, keyed by path, so that every
+/// thread's `KvBackend` handle onto the same path shares one `File` and one
+/// lock instead of racing independent file descriptors over the same bytes.
+fn shared_log(path: &str) -> anyhow::Result<Arc<Mutex<std::fs::File>>> {
+    static LOGS: OnceLock<Mutex<HashMap<String, Arc<Mutex<std::fs::File>>>>> = OnceLock::new();
+    let logs = LOGS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut logs = logs.lock().unwrap();
+    if let Some(log) = logs.get(path) {
+        return Ok(Arc::clone(log));
+    }
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    let log = Arc::new(Mutex::new(log));
+    logs.insert(path.to_string(), Arc::clone(&log));
+    Ok(log)
+}
+
+/// A minimal embedded KV engine backed by an append-only log file, so
+/// inserts survive a restart without needing a full external storage
+/// crate. Stands in for the class of pure-Rust embedded engines (e.g.
+/// sled) that `--backend kv` lets users compare SQLite against. The log
+/// file is shared (via `shared_log`) and guarded by a mutex across every
+/// handle opened onto the same path: inserts are serialized through it,
+/// and reads re-parse it on every call so a handle sees rows committed by
+/// other threads, the same parity `SqliteBackend` gets for free from its
+/// shared connection pool.
+pub struct KvBackend {
+    log: Arc<Mutex<std::fs::File>>,
+    batch_size: u64,
+    pending: u64,
+}
+
+impl KvBackend {
+    fn load_index(log: &mut std::fs::File) -> anyhow::Result<BTreeMap<Vec<u8>, Vec<u8>>> {
+        log.seek(SeekFrom::Start(0))?;
+        let mut bytes = Vec::new();
+        log.read_to_end(&mut bytes)?;
+        let mut index = BTreeMap::new();
+        let mut cursor = &bytes[..];
+        while !cursor.is_empty() {
+            let key_len = u32::from_le_bytes(cursor[0..4].try_into()?) as usize;
+            cursor = &cursor[4..];
+            let key = cursor[..key_len].to_vec();
+            cursor = &cursor[key_len..];
+            let value_len = u32::from_le_bytes(cursor[0..4].try_into()?) as usize;
+            cursor = &cursor[4..];
+            let value = cursor[..value_len].to_vec();
+            cursor = &cursor[value_len..];
+            index.insert(key, value);
+        }
+        Ok(index)
+    }
+}
+
+impl Backend for KvBackend {
+    fn open(path: &str, batch_size: u64) -> anyhow::Result<Self> {
+        let log = shared_log(path)?;
+        Ok(Self {
+            log,
+            batch_size,
+            pending: 0,
+        })
+    }
+
+    fn insert(&mut self, key: &uuid::Uuid, value: &[u8]) -> anyhow::Result<()> {
+        let key = key.as_bytes();
+        let mut log = self.log.lock().unwrap();
+        log.write_all(&(key.len() as u32).to_le_bytes())?;
+        log.write_all(key)?;
+        log.write_all(&(value.len() as u32).to_le_bytes())?;
+        log.write_all(value)?;
+        drop(log);
+        self.pending += 1;
+        if self.batch_size != 0 && self.pending >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: &uuid::Uuid) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut log = self.log.lock().unwrap();
+        let index = Self::load_index(&mut log)?;
+        Ok(index.get(key.as_bytes().as_slice()).cloned())
+    }
+
+    fn read_count(&self) -> anyhow::Result<u64> {
+        let mut log = self.log.lock().unwrap();
+        let index = Self::load_index(&mut log)?;
+        Ok(index.len() as u64)
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        let mut log = self.log.lock().unwrap();
+        log.flush()?;
+        log.sync_data()?;
+        self.pending = 0;
+        Ok(())
+    }
+}
@@ -1,12 +1,21 @@
 #![allow(dead_code, unreachable_code)]
+mod backend;
+
 use anyhow::format_err;
 use clap::*;
 use log::info;
-use rusqlite::Connection;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Barrier};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use thousands::Separable;
 
+use backend::{Backend, BackendKind, KvBackend, SqliteBackend};
+
 #[derive(Debug)]
 struct User {
     id: uuid::Uuid,
@@ -24,9 +33,85 @@ impl User {
     }
 }
 
-fn init_db(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
-    // only set if not 'memory'
-    conn.pragma_update(None, "journal_mode", "WAL")?;
+/// The tuning pragmas that most affect insert throughput, factored out of
+/// `init_db` so a `--sweep` run can drive them across a matrix of values
+/// instead of them being hardcoded.
+#[derive(Debug, Clone)]
+pub(crate) struct PragmaConfig {
+    journal_mode: String,
+    synchronous: String,
+    cache_size: i64,
+    page_size: i64,
+    mmap_size: i64,
+}
+
+impl Default for PragmaConfig {
+    fn default() -> Self {
+        Self {
+            journal_mode: "WAL".to_string(),
+            synchronous: "NORMAL".to_string(),
+            cache_size: -2000,
+            page_size: 4096,
+            mmap_size: 0,
+        }
+    }
+}
+
+impl PragmaConfig {
+    pub(crate) fn apply(&self, conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+        // page_size only takes effect on an empty database, so it must be
+        // set before any tables are created
+        conn.pragma_update(None, "page_size", self.page_size)?;
+        conn.pragma_update(None, "journal_mode", &self.journal_mode)?;
+        conn.pragma_update(None, "synchronous", &self.synchronous)?;
+        conn.pragma_update(None, "cache_size", self.cache_size)?;
+        conn.pragma_update(None, "mmap_size", self.mmap_size)?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PragmaConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "journal_mode={} synchronous={} cache_size={} page_size={} mmap_size={}",
+            self.journal_mode, self.synchronous, self.cache_size, self.page_size, self.mmap_size
+        )
+    }
+}
+
+/// The candidate values swept over by `--sweep`, combined as a cartesian
+/// product into one `PragmaConfig` per combination.
+fn sweep_configs() -> Vec<PragmaConfig> {
+    const JOURNAL_MODES: &[&str] = &["DELETE", "WAL", "MEMORY"];
+    const SYNCHRONOUS_MODES: &[&str] = &["OFF", "NORMAL", "FULL"];
+    const CACHE_SIZES: &[i64] = &[-2000, -8000];
+    const PAGE_SIZES: &[i64] = &[4096, 8192];
+    const MMAP_SIZES: &[i64] = &[0, 268_435_456];
+
+    let mut configs = Vec::new();
+    for journal_mode in JOURNAL_MODES {
+        for synchronous in SYNCHRONOUS_MODES {
+            for &cache_size in CACHE_SIZES {
+                for &page_size in PAGE_SIZES {
+                    for &mmap_size in MMAP_SIZES {
+                        configs.push(PragmaConfig {
+                            journal_mode: journal_mode.to_string(),
+                            synchronous: synchronous.to_string(),
+                            cache_size,
+                            page_size,
+                            mmap_size,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    configs
+}
+
+fn init_db(conn: &rusqlite::Connection, pragma: &PragmaConfig) -> rusqlite::Result<()> {
+    pragma.apply(conn)?;
     conn.execute(
         "CREATE TABLE users(
             id BLOB PRIMARY KEY NOT NULL,
@@ -39,6 +124,125 @@ fn init_db(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
     Ok(())
 }
 
+/// Per-operation latencies (in nanoseconds) sampled while running a
+/// workload, split by operation kind so reads and writes can be reported
+/// separately.
+#[derive(Default)]
+struct WorkloadStats {
+    insert_latencies_ns: Vec<u64>,
+    read_latencies_ns: Vec<u64>,
+}
+
+/// Ids of rows already inserted, shared across every thread in a benchmark
+/// run, so `--read-ratio` reads can target a previously-inserted row (ids
+/// are recorded as soon as the insert executes, before its transaction
+/// commits) instead of the one a thread just inserted in the same iteration - the
+/// only way to exercise real page/lock contention between readers and
+/// writers instead of a guaranteed local hit.
+#[derive(Default)]
+struct SeenIds(std::sync::Mutex<Vec<uuid::Uuid>>);
+
+impl SeenIds {
+    fn push(&self, id: uuid::Uuid) {
+        self.0.lock().unwrap().push(id);
+    }
+
+    /// A pseudo-randomly chosen previously-inserted id, excluding the most
+    /// recent push (the row the current thread just wrote, unless another
+    /// thread pushed in between). `None` until at
+    /// least two ids have been seen.
+    fn sample_older(&self) -> Option<uuid::Uuid> {
+        let ids = self.0.lock().unwrap();
+        if ids.len() < 2 {
+            return None;
+        }
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+        let idx = nanos as usize % (ids.len() - 1);
+        Some(ids[idx])
+    }
+}
+
+/// p50/p90/p99/max, in nanoseconds, of `latencies_ns`. Returns `None` if
+/// empty.
+fn percentiles(latencies_ns: &mut [u64]) -> Option<(u64, u64, u64, u64)> {
+    if latencies_ns.is_empty() {
+        return None;
+    }
+    latencies_ns.sort_unstable();
+    let at = |p: f64| -> u64 {
+        let idx = ((p * (latencies_ns.len() - 1) as f64).round() as usize).min(latencies_ns.len() - 1);
+        latencies_ns[idx]
+    };
+    Some((at(0.50), at(0.90), at(0.99), *latencies_ns.last().unwrap()))
+}
+
+fn print_latency_summary(label: &str, latencies_ns: &mut [u64]) {
+    match percentiles(latencies_ns) {
+        Some((p50, p90, p99, max)) => println!(
+            "  {label} latency (us): p50={:.1} p90={:.1} p99={:.1} max={:.1}",
+            p50 as f64 / 1_000.0,
+            p90 as f64 / 1_000.0,
+            p99 as f64 / 1_000.0,
+            max as f64 / 1_000.0
+        ),
+        None => println!("  {label} latency: no samples"),
+    }
+}
+
+/// Insert `num_inserts` rows into `users`, committing every `batch_size`
+/// inserts instead of autocommitting each one individually (`batch_size ==
+/// 0` means a single transaction spanning all `num_inserts` rows), reusing
+/// one prepared statement (via `prepare_cached`) across the whole loop.
+/// Interleaves a point lookup by id after roughly every `1 / read_ratio`
+/// inserts (`read_ratio` may be `0.0` to disable reads, or `> 1.0` to run
+/// more reads than inserts), sampling a previously-inserted id from `seen`
+/// (shared across every thread in the run) rather than the row just
+/// written, so reads can actually land on pages/rows other threads touch.
+fn run_workload(
+    conn: &rusqlite::Connection,
+    num_inserts: u64,
+    batch_size: u64,
+    read_ratio: f64,
+    seen: &SeenIds,
+) -> rusqlite::Result<WorkloadStats> {
+    let batch_size = if batch_size == 0 { num_inserts } else { batch_size };
+    let mut remaining = num_inserts;
+    let mut stats = WorkloadStats::default();
+    let mut read_acc = 0.0;
+    while remaining > 0 {
+        let this_batch = remaining.min(batch_size);
+        conn.execute_batch("BEGIN")?;
+        {
+            let mut insert_stmt = conn.prepare_cached(
+                "INSERT INTO users(id, created_at, username) VALUES (?, ?, ?)",
+            )?;
+            let mut read_stmt = conn.prepare_cached("SELECT username FROM users WHERE id = ?")?;
+            for _ in 0..this_batch {
+                let u = User::gen();
+                let t0 = Instant::now();
+                insert_stmt.execute((&u.id.to_string(), &u.created_at.to_rfc3339(), &u.username))?;
+                stats.insert_latencies_ns.push(t0.elapsed().as_nanos() as u64);
+                seen.push(u.id);
+
+                read_acc += read_ratio;
+                while read_acc >= 1.0 {
+                    if let Some(id) = seen.sample_older() {
+                        let t0 = Instant::now();
+                        let _: Option<String> = read_stmt
+                            .query_row((id.to_string(),), |row| row.get(0))
+                            .optional()?;
+                        stats.read_latencies_ns.push(t0.elapsed().as_nanos() as u64);
+                    }
+                    read_acc -= 1.0;
+                }
+            }
+        }
+        conn.execute_batch("COMMIT")?;
+        remaining -= this_batch;
+    }
+    Ok(stats)
+}
+
 fn run_reads(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
     let mut stmt = conn.prepare("SELECT count(*) from users")?;
     let count: u64 = stmt.query_row([], |row| row.get(0))?;
@@ -46,41 +250,150 @@ fn run_reads(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
     Ok(())
 }
 
-/// Sqlite inserts benchmarking based on
-/// kerkour.com/high-performance-rust-with-sqlite
-#[derive(Parser, Debug)]
-#[command(about, long_about = None)]
-struct Args {
-    /// Number of threads to spawn for concurrent inserts
-    #[arg(short = 'c', long = "concurrency", default_value_t = 1)]
-    num_threads: u64,
+/// One measured workload within a run, e.g. "insert".
+struct TaskResult {
+    name: &'static str,
+    total_iterations: u64,
+    measured_ns: u64,
+    inserts_per_sec: f64,
+}
 
-    /// Number of inserts per thread
-    #[arg(short, long, default_value_t = 1)]
-    num_inserts_per_thread: u64,
+/// Result of `git rev-parse HEAD` in the current working directory, or
+/// `None` if git isn't available / this isn't a git checkout.
+fn current_commit_id() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit_id = String::from_utf8(output.stdout).ok()?;
+    Some(commit_id.trim().to_string())
 }
 
-fn main() -> anyhow::Result<()> {
-    pretty_env_logger::init();
+fn open_history_db(path: &std::path::Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS bench(
+            id INTEGER PRIMARY KEY,
+            time INTEGER NOT NULL,
+            name TEXT,
+            title TEXT,
+            tag TEXT,
+            commit_id TEXT
+        )",
+        (),
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS task(
+            id INTEGER PRIMARY KEY,
+            bench INTEGER NOT NULL REFERENCES bench(id),
+            name TEXT NOT NULL,
+            total_iterations INTEGER NOT NULL,
+            measured_ns INTEGER NOT NULL,
+            inserts_per_sec REAL NOT NULL
+        )",
+        (),
+    )?;
+    Ok(conn)
+}
 
-    // get CLI args
-    let Args {
-        num_inserts_per_thread,
-        num_threads,
-    } = Args::parse();
-    let num_inserts = num_inserts_per_thread * num_threads;
-    info!(
-        "inserts: {}, concurrency: {}",
-        num_inserts.separate_with_commas(),
-        num_threads
-    );
+/// Record a run's task results into the history db and print a
+/// regression/improvement delta against the most recent prior run of each
+/// task, if one exists.
+fn record_history(
+    history_db_path: &std::path::Path,
+    name: &str,
+    title: &str,
+    tag: Option<&str>,
+    tasks: &[TaskResult],
+) -> rusqlite::Result<()> {
+    let conn = open_history_db(history_db_path)?;
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let commit_id = current_commit_id();
 
-    // common
-    let db_path = "db.sqlite";
-    let get_conn = move || Connection::open(&db_path);
+    conn.execute(
+        "INSERT INTO bench(time, name, title, tag, commit_id) VALUES (?, ?, ?, ?, ?)",
+        (&time, &name, &title, &tag, &commit_id),
+    )?;
+    let bench_id = conn.last_insert_rowid();
+
+    for task in tasks {
+        // find the most recent prior run of this task before we insert ours
+        let prior: Option<f64> = conn
+            .query_row(
+                "SELECT task.inserts_per_sec
+                 FROM task JOIN bench ON task.bench = bench.id
+                 WHERE task.name = ? AND bench.id != ?
+                 ORDER BY bench.time DESC LIMIT 1",
+                (&task.name, &bench_id),
+                |row| row.get(0),
+            )
+            .ok();
+
+        conn.execute(
+            "INSERT INTO task(bench, name, total_iterations, measured_ns, inserts_per_sec)
+             VALUES (?, ?, ?, ?, ?)",
+            (
+                &bench_id,
+                &task.name,
+                &task.total_iterations,
+                &task.measured_ns,
+                &task.inserts_per_sec,
+            ),
+        )?;
+
+        match prior {
+            Some(prior_rate) if prior_rate > 0.0 => {
+                let delta = (task.inserts_per_sec - prior_rate) / prior_rate * 100.0;
+                println!(
+                    "[history] task `{}`: {:+.1}% vs previous run ({} inserts/s)",
+                    task.name,
+                    delta,
+                    prior_rate.round().separate_with_commas()
+                );
+            }
+            _ => {
+                println!(
+                    "[history] task `{}`: no prior run to compare against",
+                    task.name
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Outcome of one `run_benchmark` invocation.
+struct BenchOutcome {
+    duration: Duration,
+    inserts_per_sec: f64,
+    per_thread_durations: Vec<(u64, Duration)>,
+    insert_latencies_ns: Vec<u64>,
+    read_latencies_ns: Vec<u64>,
+}
+
+/// Run the concurrent insert benchmark once against a fresh `db_path`,
+/// tuned with `pragma`. Re-creates the `users` table so callers (e.g. a
+/// `--sweep`) can re-run this back to back under different pragmas without
+/// leftover rows skewing the comparison.
+fn run_benchmark(
+    db_path: &str,
+    num_threads: u64,
+    num_inserts_per_thread: u64,
+    batch_size: u64,
+    read_ratio: f64,
+    pragma: &PragmaConfig,
+) -> anyhow::Result<BenchOutcome> {
+    let num_inserts = num_inserts_per_thread * num_threads;
 
     // delete db if it already exists
-    if let Err(e) = std::fs::remove_file(&db_path) {
+    if let Err(e) = std::fs::remove_file(db_path) {
         if e.kind() != std::io::ErrorKind::NotFound {
             return Err(format_err!("{}: {}", e, db_path));
         }
@@ -88,43 +401,305 @@ fn main() -> anyhow::Result<()> {
 
     // create table
     {
-        let conn = get_conn()?;
-        init_db(&conn).unwrap();
+        let conn = Connection::open(db_path)?;
+        init_db(&conn, pragma).unwrap();
     }
 
-    // start timing
-    let start = Instant::now();
-    let num_threads = 1; // focus on single-threaded for now
+    // separate small read/write pools: writers contend for the single WAL
+    // writer lock so there's no point sizing that pool beyond num_threads,
+    // while readers can run concurrently against the WAL snapshot
+    let write_pool = Pool::builder()
+        .max_size(num_threads as u32)
+        .build(SqliteConnectionManager::file(db_path))?;
+    let read_pool = Pool::builder()
+        .max_size(num_threads.min(4) as u32)
+        .build(SqliteConnectionManager::file(db_path))?;
+
+    // every thread blocks here until all threads have a connection in hand,
+    // so the measured window starts at the same instant for everyone rather
+    // than being skewed by thread-spawn/connection-acquire jitter (the main
+    // thread waits too, and starts the aggregate clock on release)
+    let barrier = Arc::new(Barrier::new(num_threads as usize + 1));
+    let seen = Arc::new(SeenIds::default());
 
     // run concurrent inserts
     let mut handles = Vec::with_capacity(num_threads as usize);
     for i in 1..=num_threads {
-        let handle = thread::spawn(move || -> anyhow::Result<()> {
+        let write_pool = write_pool.clone();
+        let barrier = Arc::clone(&barrier);
+        let pragma = pragma.clone();
+        let seen = Arc::clone(&seen);
+        let handle = thread::spawn(move || -> anyhow::Result<(u64, Duration, WorkloadStats)> {
+            let thread_id = format!("[thread {}]", i);
+            info!("{thread_id} start");
+            let conn = write_pool.get()?;
+            pragma.apply(&conn)?;
+            barrier.wait();
+            let thread_start = Instant::now();
+            let stats = run_workload(&conn, num_inserts_per_thread, batch_size, read_ratio, &seen)?;
+            let thread_duration = thread_start.elapsed();
+            info!("{thread_id} complete");
+            Ok((i, thread_duration, stats))
+        });
+        handles.push(handle);
+    }
+    barrier.wait();
+    let start = Instant::now();
+
+    let mut per_thread_durations = Vec::with_capacity(handles.len());
+    let mut insert_latencies_ns = Vec::new();
+    let mut read_latencies_ns = Vec::new();
+    for handle in handles {
+        let (i, thread_duration, stats) = handle.join().unwrap()?;
+        per_thread_durations.push((i, thread_duration));
+        insert_latencies_ns.extend(stats.insert_latencies_ns);
+        read_latencies_ns.extend(stats.read_latencies_ns);
+    }
+
+    let duration = start.elapsed();
+    let inserts_per_sec = num_inserts as f64 / duration.as_secs_f64();
+
+    // get number of inserts
+    {
+        let conn = read_pool.get()?;
+        run_reads(&conn)?;
+    }
+
+    Ok(BenchOutcome {
+        duration,
+        inserts_per_sec,
+        per_thread_durations,
+        insert_latencies_ns,
+        read_latencies_ns,
+    })
+}
+
+/// Same insert/read workload as `run_benchmark`, but driven through the
+/// `Backend` trait so it can run against any storage engine, not just
+/// rusqlite. Each thread opens its own backend handle onto `path`.
+fn run_generic_benchmark<B: Backend>(
+    path: &str,
+    num_threads: u64,
+    num_inserts_per_thread: u64,
+    batch_size: u64,
+    read_ratio: f64,
+) -> anyhow::Result<BenchOutcome> {
+    let num_inserts = num_inserts_per_thread * num_threads;
+
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(format_err!("{}: {}", e, path));
+        }
+    }
+
+    let barrier = Arc::new(Barrier::new(num_threads as usize));
+    let seen = Arc::new(SeenIds::default());
+    let path = path.to_string();
+
+    let start = Instant::now();
+    let mut handles = Vec::with_capacity(num_threads as usize);
+    for i in 1..=num_threads {
+        let barrier = Arc::clone(&barrier);
+        let seen = Arc::clone(&seen);
+        let path = path.clone();
+        let handle = thread::spawn(move || -> anyhow::Result<(u64, Duration, WorkloadStats)> {
             let thread_id = format!("[thread {}]", i);
             info!("{thread_id} start");
-            let conn = get_conn().unwrap();
-            conn.pragma_update(None, "synchronous", "NORMAL")?;
+            let mut backend = B::open(&path, batch_size)?;
+            barrier.wait();
+            let thread_start = Instant::now();
+            let mut stats = WorkloadStats::default();
+            let mut read_acc = 0.0;
             for _ in 0..num_inserts_per_thread {
                 let u = User::gen();
-                conn.execute(
-                    "INSERT INTO users(id, created_at, username) VALUES (?, ?, ?)",
-                    (&u.id.to_string(), &u.created_at.to_rfc3339(), &u.username),
-                )
-                .unwrap();
+                let value = format!("{}\t{}", u.created_at.to_rfc3339(), u.username);
+                let t0 = Instant::now();
+                backend.insert(&u.id, value.as_bytes())?;
+                stats.insert_latencies_ns.push(t0.elapsed().as_nanos() as u64);
+                seen.push(u.id);
+
+                read_acc += read_ratio;
+                while read_acc >= 1.0 {
+                    if let Some(id) = seen.sample_older() {
+                        let t0 = Instant::now();
+                        backend.get(&id)?;
+                        stats.read_latencies_ns.push(t0.elapsed().as_nanos() as u64);
+                    }
+                    read_acc -= 1.0;
+                }
             }
+            backend.flush()?;
+            let thread_duration = thread_start.elapsed();
             info!("{thread_id} complete");
-            Ok(())
+            Ok((i, thread_duration, stats))
         });
         handles.push(handle);
     }
 
+    let mut per_thread_durations = Vec::with_capacity(handles.len());
+    let mut insert_latencies_ns = Vec::new();
+    let mut read_latencies_ns = Vec::new();
     for handle in handles {
-        handle.join().unwrap()?;
+        let (i, thread_duration, stats) = handle.join().unwrap()?;
+        per_thread_durations.push((i, thread_duration));
+        insert_latencies_ns.extend(stats.insert_latencies_ns);
+        read_latencies_ns.extend(stats.read_latencies_ns);
     }
 
-    // get duration
     let duration = start.elapsed();
     let inserts_per_sec = num_inserts as f64 / duration.as_secs_f64();
+
+    let backend = B::open(&path, batch_size)?;
+    let count = backend.read_count()?;
+    println!("num records stored: {}", count);
+
+    Ok(BenchOutcome {
+        duration,
+        inserts_per_sec,
+        per_thread_durations,
+        insert_latencies_ns,
+        read_latencies_ns,
+    })
+}
+
+/// Sqlite inserts benchmarking based on
+/// kerkour.com/high-performance-rust-with-sqlite
+#[derive(Parser, Debug)]
+#[command(about, long_about = None)]
+struct Args {
+    /// Number of threads to spawn for concurrent inserts
+    #[arg(
+        short = 'c',
+        long = "concurrency",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    num_threads: u64,
+
+    /// Number of inserts per thread
+    #[arg(short, long, default_value_t = 1)]
+    num_inserts_per_thread: u64,
+
+    /// Path to a separate SQLite database that benchmark runs are recorded
+    /// into, so results can be tracked and compared across commits
+    #[arg(long)]
+    history_db: Option<PathBuf>,
+
+    /// Commit every N inserts instead of autocommitting each one. 0 means a
+    /// single transaction spanning every insert done by a thread
+    #[arg(long, default_value_t = 1)]
+    batch_size: u64,
+
+    /// Instead of a single run, sweep the insert benchmark across a
+    /// cartesian product of journal_mode/synchronous/cache_size/page_size/
+    /// mmap_size and print a sorted table of config -> inserts/sec
+    #[arg(long, default_value_t = false)]
+    sweep: bool,
+
+    /// Storage engine to run the insert/read workload against, via the
+    /// generic `Backend` trait. Omit to use the default, batch-tuned sqlite
+    /// path (the only one `--sweep` can drive)
+    #[arg(long, value_enum)]
+    backend: Option<BackendKind>,
+
+    /// Interleave a point lookup by id with inserts at this ratio (e.g. 0.1
+    /// = 1 read per 10 inserts, 2.0 = 2 reads per insert). 0 disables reads
+    #[arg(long, default_value_t = 0.0)]
+    read_ratio: f64,
+}
+
+fn main() -> anyhow::Result<()> {
+    pretty_env_logger::init();
+
+    // get CLI args
+    let Args {
+        num_inserts_per_thread,
+        num_threads,
+        history_db,
+        batch_size,
+        sweep,
+        backend,
+        read_ratio,
+    } = Args::parse();
+    let num_inserts = num_inserts_per_thread * num_threads;
+    info!(
+        "inserts: {}, concurrency: {}",
+        num_inserts.separate_with_commas(),
+        num_threads
+    );
+
+    let db_path = "db.sqlite";
+
+    if sweep {
+        if backend.is_some() {
+            return Err(format_err!(
+                "--sweep only tunes the default sqlite path; drop --backend to use it"
+            ));
+        }
+        let configs = sweep_configs();
+        info!("sweeping {} pragma configs", configs.len());
+        let mut results = Vec::with_capacity(configs.len());
+        for pragma in configs {
+            let outcome = run_benchmark(
+                db_path,
+                num_threads,
+                num_inserts_per_thread,
+                batch_size,
+                read_ratio,
+                &pragma,
+            )?;
+            results.push((pragma, outcome.inserts_per_sec));
+        }
+        results.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        let col_width = results
+            .iter()
+            .map(|(pragma, _)| pragma.to_string().len())
+            .max()
+            .unwrap_or(0)
+            + 2;
+        println!("{:<col_width$}inserts/s", "config");
+        for (pragma, inserts_per_sec) in &results {
+            println!(
+                "{:<col_width$}{}",
+                pragma.to_string(),
+                inserts_per_sec.round().separate_with_commas()
+            );
+        }
+        return Ok(());
+    }
+
+    let outcome = match backend {
+        None => run_benchmark(
+            db_path,
+            num_threads,
+            num_inserts_per_thread,
+            batch_size,
+            read_ratio,
+            &PragmaConfig::default(),
+        )?,
+        Some(BackendKind::Sqlite) => run_generic_benchmark::<SqliteBackend>(
+            db_path,
+            num_threads,
+            num_inserts_per_thread,
+            batch_size,
+            read_ratio,
+        )?,
+        Some(BackendKind::Kv) => run_generic_benchmark::<KvBackend>(
+            db_path,
+            num_threads,
+            num_inserts_per_thread,
+            batch_size,
+            read_ratio,
+        )?,
+    };
+    let BenchOutcome {
+        duration,
+        inserts_per_sec,
+        per_thread_durations,
+        mut insert_latencies_ns,
+        mut read_latencies_ns,
+    } = outcome;
+
     println!(
         "Benchmark: insert {} records ({}/{}): {:?} ({} inserts/s)",
         num_inserts.separate_with_commas(),
@@ -133,11 +708,29 @@ fn main() -> anyhow::Result<()> {
         duration,
         inserts_per_sec.round().separate_with_commas()
     );
+    for (thread_num, thread_duration) in &per_thread_durations {
+        println!("  [thread {}] completed in {:?}", thread_num, thread_duration);
+    }
+    print_latency_summary("insert", &mut insert_latencies_ns);
+    if read_ratio > 0.0 {
+        print_latency_summary("read", &mut read_latencies_ns);
+    }
 
-    // get number of inserts
-    {
-        let conn = get_conn()?;
-        run_reads(&conn)?;
+    // persist this run to the history db and report any regression
+    if let Some(history_db_path) = history_db {
+        let task = TaskResult {
+            name: "insert",
+            total_iterations: num_inserts,
+            measured_ns: duration.as_nanos() as u64,
+            inserts_per_sec,
+        };
+        record_history(
+            &history_db_path,
+            env!("CARGO_PKG_NAME"),
+            "insert benchmark",
+            None,
+            &[task],
+        )?;
     }
     Ok(())
 }